@@ -10,12 +10,54 @@ use zkp_toolkit::clinkv2::kzg10::{
 use zkp_toolkit::clinkv2::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
 use zkp_toolkit::math::{Field, One, PrimeField, Zero};
 
+mod batch;
+mod mimc;
+mod poseidon;
+mod range_check;
+mod solidity;
+use batch::{create_random_proof_batch, tile_grid, verify_proofs_batch};
+use mimc::{mimc as mimc_native, mimc_gadget, round_constants as mimc_round_constants};
+use poseidon::{
+    mds_matrix, poseidon as poseidon_native, poseidon_gadget,
+    round_constants as poseidon_round_constants,
+};
+use range_check::range_check_pixel;
+use solidity::{encode_calldata, render_solidity_verifier};
+
+// Tile side length, in blocks. A full image is proved as independent
+// `TILE_SIDE x TILE_SIDE`-block tiles instead of one proof over every
+// block, so proving can be parallelized across tiles and each individual
+// proof stays small.
+const TILE_SIDE: u32 = 4;
+
+// Which source-commitment hash `Thumbnail` binds the proof to: 322-round
+// MiMC (see `mimc`) or the cheaper Poseidon permutation (see `poseidon`).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum HashKind {
+    Mimc,
+    Poseidon,
+}
+
+// Selects the commitment hash used by every `Thumbnail` in this run.
+const HASH_KIND: HashKind = HashKind::Poseidon;
+
 // Single round.
 struct Thumbnail<F: PrimeField> {
     pub inps: [Option<F>; 100], // inputs pixel. 100-ratio
     pub out: Option<F>,         // outputs pixel.
     //pub p: Option<F>,           //position.
     pub p: u32,
+    // Commitment to `inps`, computed natively by the prover ahead of time
+    // with whichever hash `hash_kind` selects (see `mimc::mimc` /
+    // `poseidon::poseidon`). Exposed as a public input so the verifier can
+    // check the proof is tied to this specific source block.
+    pub digest: Option<F>,
+    pub hash_kind: HashKind,
+    // Raw RGBA bytes backing `inps`/`out`, used as witnesses for
+    // `range_check::range_check_pixel` so a dishonest prover can't pack an
+    // out-of-range field element into a pixel. `None` for the verifier.
+    pub inp_bytes: [Option<[u8; 4]>; 100],
+    pub out_bytes: Option<[u8; 4]>,
 }
 
 impl<F: PrimeField> ConstraintSynthesizer<F> for Thumbnail<F> {
@@ -42,14 +84,48 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for Thumbnail<F> {
             index,
         )?;
 
-        if index == 0 {
-            cs.enforce(
-                || "x * (y + 2) = z",
-                |lc| lc + var_inps[self.p as usize],
-                |lc| lc + CS::one(),
-                |lc| lc + var_o,
-            );
+        cs.enforce(
+            || "inps[p] * 1 = out",
+            |lc| lc + var_inps[self.p as usize],
+            |lc| lc + CS::one(),
+            |lc| lc + var_o,
+        );
+
+        let pixel_values: Vec<Option<F>> = self.inps.to_vec();
+        let (var_digest, digest_val) = match self.hash_kind {
+            HashKind::Mimc => {
+                let constants = mimc_round_constants::<F>();
+                mimc_gadget(cs, index, &var_inps, &pixel_values, &constants)?
+            }
+            HashKind::Poseidon => {
+                let constants = poseidon_round_constants::<F>();
+                let mds = mds_matrix::<F>();
+                poseidon_gadget(cs, index, &var_inps, &pixel_values, &constants, &mds)?
+            }
+        };
+        let var_digest_input = cs.alloc_input(
+            || "commit(digest)",
+            || self.digest.ok_or(SynthesisError::AssignmentMissing),
+            index,
+        )?;
+        cs.enforce(
+            || "commit(digest) = hash(inputs)",
+            |lc| lc + var_digest,
+            |lc| lc + CS::one(),
+            |lc| lc + var_digest_input,
+        );
+        let _ = digest_val;
+
+        for (i, var) in var_inps.iter().enumerate() {
+            range_check_pixel(
+                cs,
+                index,
+                &format!("range(input {})", i),
+                *var,
+                self.inp_bytes[i],
+            )?;
         }
+        range_check_pixel(cs, index, "range(output)", var_o, self.out_bytes)?;
 
         Ok(())
     }
@@ -80,13 +156,15 @@ fn main() {
     let mut rng = thread_rng();
 
     println!("Running mimc_clinkv2...");
-    let m = new_x * new_y;
+    let tiles = tile_grid(new_x, new_y, TILE_SIDE);
+    let max_tile_blocks = (TILE_SIDE * TILE_SIDE) as usize;
 
     // println!("Creating KZG10 parameters...");
-    let degree = m.next_power_of_two() as usize;
+    let degree = max_tile_blocks.next_power_of_two();
     let mut crs_time = Duration::new(0, 0);
 
-    // Create parameters for our circuit
+    // Create parameters for our circuit. Every tile shares the same degree
+    // (bounded by the largest possible tile), so one CRS serves all tiles.
     let start = Instant::now();
 
     let kzg10_pp = KZG10::<Bn_256>::setup(degree, false, &mut rng).unwrap();
@@ -98,43 +176,118 @@ fn main() {
     // Prover
     let prove_start = Instant::now();
 
-    let mut prover_pa = ProveAssignment::<Bn_256>::default();
     let mut out_file = ImageBuffer::new(new_x, new_y);
 
-    for m_x in 0..new_x {
-        for m_y in 0..new_y {
-            let mut tmp_pixels = [Some(Fr::zero()); 100];
-            for i in 0..n {
-                for j in 0..n {
-                    let tmp_x = m_x * n + i;
-                    let tmp_y = m_y * n + j;
-                    let pixel = img.get_pixel(tmp_x, tmp_y).to_rgba();
-                    let fr = Fr::from_random_bytes(as_bytes(&pixel)).unwrap();
-                    if i * n + j == p {
-                        out_file.put_pixel(m_x, m_y, pixel);
+    let mimc_constants = match HASH_KIND {
+        HashKind::Mimc => Some(mimc_round_constants::<Fr>()),
+        HashKind::Poseidon => None,
+    };
+    let poseidon_params = match HASH_KIND {
+        HashKind::Mimc => None,
+        HashKind::Poseidon => Some((poseidon_round_constants::<Fr>(), mds_matrix::<Fr>())),
+    };
+    let mut tile_pas = vec![];
+    let mut tile_ios: Vec<Vec<Vec<Fr>>> = vec![];
+
+    for tile in &tiles {
+        let mut tile_pa = ProveAssignment::<Bn_256>::default();
+        let mut digest_fr = vec![];
+        let mut output_fr = vec![];
+        let mut local_index = 0usize;
+        let mut last_block = None;
+
+        for m_x in tile.x0..tile.x1 {
+            for m_y in tile.y0..tile.y1 {
+                let mut tmp_pixels = [Some(Fr::zero()); 100];
+                let mut tmp_pixel_bytes = [Some([0u8; 4]); 100];
+                let mut tmp_out_bytes = None;
+                for i in 0..n {
+                    for j in 0..n {
+                        let tmp_x = m_x * n + i;
+                        let tmp_y = m_y * n + j;
+                        let pixel = img.get_pixel(tmp_x, tmp_y).to_rgba();
+                        let pixel_bytes: [u8; 4] = as_bytes(&pixel).try_into().unwrap();
+                        let fr = Fr::from_random_bytes(as_bytes(&pixel)).unwrap();
+                        if i * n + j == p {
+                            out_file.put_pixel(m_x, m_y, pixel);
+                            tmp_out_bytes = Some(pixel_bytes);
+                        }
+                        tmp_pixels[(i * n + j) as usize] = Some(fr);
+                        tmp_pixel_bytes[(i * n + j) as usize] = Some(pixel_bytes);
                     }
-                    tmp_pixels[(i * n + j) as usize] = Some(fr);
                 }
+                let tmp_out = tmp_pixels[p as usize].clone();
+
+                let tmp_pixels_fr: Vec<Fr> = tmp_pixels.iter().map(|x| x.unwrap()).collect();
+                let digest = match HASH_KIND {
+                    HashKind::Mimc => {
+                        mimc_native(&tmp_pixels_fr, mimc_constants.as_ref().unwrap())
+                    }
+                    HashKind::Poseidon => {
+                        let (constants, mds) = poseidon_params.as_ref().unwrap();
+                        poseidon_native(&tmp_pixels_fr, constants, mds)
+                    }
+                };
+                digest_fr.push(digest);
+                output_fr.push(tmp_out.unwrap());
+
+                let c = Thumbnail {
+                    inps: tmp_pixels,
+                    out: tmp_out,
+                    p: p,
+                    digest: Some(digest),
+                    hash_kind: HASH_KIND,
+                    inp_bytes: tmp_pixel_bytes,
+                    out_bytes: tmp_out_bytes,
+                };
+                c.generate_constraints(&mut tile_pa, local_index).unwrap();
+                local_index += 1;
+                last_block = Some((tmp_pixels, tmp_out, digest, tmp_pixel_bytes, tmp_out_bytes));
             }
-            let tmp_out = tmp_pixels[p as usize].clone();
+        }
 
+        // Edge tiles (wherever the block grid isn't an exact multiple of
+        // `TILE_SIDE`) have fewer real blocks than `max_tile_blocks`.
+        // `verify_proofs_batch` folds every tile's proof and public-input
+        // rows together via a fixed-degree linear combination, so every
+        // tile must carry the same number of copies -- pad by re-proving
+        // the tile's last real block until it does. The padding copies are
+        // genuine, already-valid witnesses (not masked/blank ones), so
+        // they don't relax what's being checked.
+        let (tmp_pixels, tmp_out, digest, tmp_pixel_bytes, tmp_out_bytes) =
+            last_block.expect("a tile always has at least one block");
+        while local_index < max_tile_blocks {
+            digest_fr.push(digest);
+            output_fr.push(tmp_out.unwrap());
             let c = Thumbnail {
                 inps: tmp_pixels,
                 out: tmp_out,
                 p: p,
+                digest: Some(digest),
+                hash_kind: HASH_KIND,
+                inp_bytes: tmp_pixel_bytes,
+                out_bytes: tmp_out_bytes,
             };
-            c.generate_constraints(&mut prover_pa, (m_x * new_y + m_y) as usize)
-                .unwrap();
+            c.generate_constraints(&mut tile_pa, local_index).unwrap();
+            local_index += 1;
         }
+
+        let one = vec![Fr::one(); max_tile_blocks];
+        tile_ios.push(vec![one, output_fr, digest_fr]);
+        tile_pas.push(tile_pa);
     }
 
     println!("Create prove...");
-    // Create a clinkv2 proof with our parameters.
-    let proof = create_random_proof(&prover_pa, &kzg10_ck, &mut rng).unwrap();
+    // Create one clinkv2 proof per tile.
+    let proofs = create_random_proof_batch(&tile_pas, &kzg10_ck, &mut rng).unwrap();
     let prove_time = prove_start.elapsed();
 
     out_file.save("test.png").unwrap();
-    println!("Thumbnail image created: blocks: {}", new_x * new_y);
+    println!(
+        "Thumbnail image created: blocks: {}, tiles: {}",
+        new_x * new_y,
+        tiles.len()
+    );
 
     // Verifier
     println!("Start verify prepare...");
@@ -142,11 +295,17 @@ fn main() {
 
     let mut verifier_pa = VerifyAssignment::<Bn_256>::default();
 
-    // Create an instance of our circuit (with the witness)
+    // Create an instance of our circuit (without the witness). Every tile
+    // shares this same circuit structure, so it only needs synthesizing
+    // once.
     let verify_c = Thumbnail {
         inps: [None; 100],
         out: None,
         p: p,
+        digest: None,
+        hash_kind: HASH_KIND,
+        inp_bytes: [None; 100],
+        out_bytes: None,
     };
     verify_c
         .generate_constraints(&mut verifier_pa, 0usize)
@@ -154,26 +313,26 @@ fn main() {
 
     println!("Start verify...");
 
-    let mut io: Vec<Vec<Fr>> = vec![];
-    let mut output_fr = vec![];
-
-    for m_x in 0..new_x {
-        for m_y in 0..new_y {
-            let pixel = out_file.get_pixel(m_x, m_y).to_rgba();
-            let fr = Fr::from_random_bytes(as_bytes(&pixel)).unwrap();
-            output_fr.push(fr);
-        }
-    }
-
-    let one = vec![Fr::one(); (new_x * new_y) as usize];
-    io.push(one);
-    io.push(output_fr);
-
-    // Check the proof
-    assert!(verify_proof(&verifier_pa, &kzg10_vk, &proof, &io).unwrap());
+    // Check every tile's proof in one batched, random-linear-combined
+    // pairing check.
+    assert!(
+        verify_proofs_batch(&verifier_pa, &kzg10_vk, &proofs, &tile_ios, &mut rng).unwrap()
+    );
 
     let verify_time = verify_start.elapsed();
 
+    // On-chain verifier: render a Solidity contract for `kzg10_vk` and the
+    // calldata for the first tile's proof, so the proof can also be
+    // checked by a smart contract instead of `verify_proof`.
+    let num_public_inputs = tile_ios[0].iter().map(|v| v.len()).sum();
+    let verifier_sol = render_solidity_verifier(&kzg10_vk, num_public_inputs);
+    std::fs::write("ThumbnailVerifier.sol", verifier_sol).unwrap();
+    let calldata = encode_calldata(&proofs[0], &tile_ios[0]);
+    println!(
+        "Wrote ThumbnailVerifier.sol, first-tile calldata: {} bytes",
+        calldata.len()
+    );
+
     // Compute time
 
     let proving_avg =
@@ -0,0 +1,152 @@
+// LongsightF322p3 MiMC, used to bind a thumbnail proof to a commitment of
+// the source pixels that went into it.
+use zkp_toolkit::clinkv2::r1cs::{ConstraintSystem, SynthesisError, Variable};
+use zkp_toolkit::math::{Field, PrimeField, Zero};
+
+// Number of rounds for the "LongsightF322p3" instantiation (322 rounds, a
+// cubic S-box, over a ~254-bit prime field).
+pub const MIMC_ROUNDS: usize = 322;
+
+// Fixed, publicly known round constants. Derived deterministically from a
+// counter so the prover and the verifier always agree on the same values
+// without shipping a constants table around.
+pub fn round_constants<F: PrimeField>() -> Vec<F> {
+    (0..MIMC_ROUNDS)
+        .map(|i| {
+            let mut seed = [0u8; 32];
+            seed[0..8].copy_from_slice(b"mimc-rc\0");
+            seed[8] = (i & 0xff) as u8;
+            seed[9] = ((i >> 8) & 0xff) as u8;
+            F::from_random_bytes(&seed).expect("round constant seed must parse")
+        })
+        .collect()
+}
+
+// One LongsightF322p3 round: xL, xR := xR + (xL + C_i)^3, xL
+fn round<F: PrimeField>(xl: F, xr: F, c: F) -> (F, F) {
+    let t = xl + c;
+    let t2 = t * t;
+    let t3 = t2 * t;
+    (xr + t3, xl)
+}
+
+// Native reference implementation: absorbs `inputs` one at a time into a
+// sponge state `(xL, xR)` starting from zero, running the full permutation
+// after each absorption. Used outside the circuit so the prover can compute
+// the expected digest to hand to the verifier as a public input.
+pub fn mimc<F: PrimeField>(inputs: &[F], constants: &[F]) -> F {
+    let mut xl = F::zero();
+    let mut xr = F::zero();
+    for x in inputs {
+        xl = xl + *x;
+        for c in constants {
+            let (next_xl, next_xr) = round(xl, xr, *c);
+            xl = next_xl;
+            xr = next_xr;
+        }
+    }
+    xl
+}
+
+// In-circuit counterpart of `mimc`. Absorbs the already-allocated `pixels`
+// into the sponge, enforcing every cube via two multiplication
+// constraints (t2 = t * t, t3 = t2 * t), and returns the variable holding
+// the final digest together with its value (for the prover; `None` for the
+// verifier).
+pub fn mimc_gadget<F: PrimeField, CS: ConstraintSystem<F>>(
+    cs: &mut CS,
+    index: usize,
+    pixels: &[Variable],
+    pixel_values: &[Option<F>],
+    constants: &[F],
+) -> Result<(Variable, Option<F>), SynthesisError> {
+    // `xl`/`xr` track the sponge state as a variable (after the first
+    // absorption there is always at least one allocated term) plus its
+    // value for witness generation.
+    let mut xl_var = cs.alloc(|| "mimc(xl0)", || Ok(F::zero()), index)?;
+    let mut xr_var = cs.alloc(|| "mimc(xr0)", || Ok(F::zero()), index)?;
+    let mut xl_val = Some(F::zero());
+    let mut xr_val = Some(F::zero());
+    cs.enforce(
+        || "mimc(xl0) = 0",
+        |lc| lc + xl_var,
+        |lc| lc + CS::one(),
+        |lc| lc,
+    );
+    cs.enforce(
+        || "mimc(xr0) = 0",
+        |lc| lc + xr_var,
+        |lc| lc + CS::one(),
+        |lc| lc,
+    );
+
+    for (pixel_var, pixel_val) in pixels.iter().zip(pixel_values.iter()) {
+        // Absorb: xl := xl + pixel
+        let absorbed_val = xl_val.and_then(|xl| pixel_val.map(|p| xl + p));
+        let absorbed_var = cs.alloc(|| "mimc(absorb)", || {
+            absorbed_val.ok_or(SynthesisError::AssignmentMissing)
+        }, index)?;
+        cs.enforce(
+            || "mimc(absorb) = xl + pixel",
+            |lc| lc + xl_var + *pixel_var,
+            |lc| lc + CS::one(),
+            |lc| lc + absorbed_var,
+        );
+        xl_var = absorbed_var;
+        xl_val = absorbed_val;
+
+        for (round_idx, c) in constants.iter().enumerate() {
+            let t_val = xl_val.map(|xl| xl + *c);
+            let t2_var = cs.alloc(
+                || format!("mimc(t2, round {})", round_idx),
+                || {
+                    let t = t_val.ok_or(SynthesisError::AssignmentMissing)?;
+                    Ok(t * t)
+                },
+                index,
+            )?;
+            cs.enforce(
+                || format!("mimc(t2, round {}) = t * t", round_idx),
+                |lc| lc + xl_var + (*c, CS::one()),
+                |lc| lc + xl_var + (*c, CS::one()),
+                |lc| lc + t2_var,
+            );
+
+            let t3_var = cs.alloc(
+                || format!("mimc(t3, round {})", round_idx),
+                || {
+                    let t = t_val.ok_or(SynthesisError::AssignmentMissing)?;
+                    let t2 = t * t;
+                    Ok(t2 * t)
+                },
+                index,
+            )?;
+            cs.enforce(
+                || format!("mimc(t3, round {}) = t2 * t", round_idx),
+                |lc| lc + t2_var,
+                |lc| lc + xl_var + (*c, CS::one()),
+                |lc| lc + t3_var,
+            );
+
+            let next_xl_val = xr_val.and_then(|xr| t_val.map(|t| xr + t * t * t));
+            let next_xl_var = cs.alloc(
+                || format!("mimc(xl, round {})", round_idx),
+                || next_xl_val.ok_or(SynthesisError::AssignmentMissing),
+                index,
+            )?;
+            cs.enforce(
+                || format!("mimc(xl, round {}) = xr + t3", round_idx),
+                |lc| lc + xr_var + t3_var,
+                |lc| lc + CS::one(),
+                |lc| lc + next_xl_var,
+            );
+
+            xr_var = xl_var;
+            xr_val = xl_val;
+            xl_var = next_xl_var;
+            xl_val = next_xl_val;
+        }
+    }
+
+    Ok((xl_var, xl_val))
+}
@@ -0,0 +1,442 @@
+// Codegen for an on-chain (Solidity/EVM) verifier of the KZG10 clinkv2
+// proof, plus a matching calldata encoder, so a thumbnail proof produced by
+// `create_random_proof` can be checked by a smart contract.
+use zkp_toolkit::bn_256::{Bn_256, Fr};
+use zkp_toolkit::clinkv2::kzg10::{Proof, VerifierKey};
+use zkp_toolkit::math::ToBytes;
+
+// Byte width of one uncompressed BN254 G2 point (two Fq2 coordinates, each
+// two 32-byte limbs) in `zkp_toolkit`'s `ToBytes` encoding.
+const G2_POINT_BYTES: usize = 128;
+
+// Reads a `ToBytes`-serialized uncompressed G2 point out of `bytes` at
+// `offset` and formats it as the four hex words `Pairing.G2Point` expects
+// (`x[0], x[1], y[0], y[1]`).
+fn g2_point_hex(bytes: &[u8], offset: usize) -> [String; 4] {
+    let mut words = [0u8; 4 * 32];
+    words.copy_from_slice(&bytes[offset..offset + G2_POINT_BYTES]);
+    std::array::from_fn(|i| {
+        let limb = &words[i * 32..(i + 1) * 32];
+        format!("0x{}", limb.iter().map(|b| format!("{:02x}", b)).collect::<String>())
+    })
+}
+
+// Renders a standalone Solidity verifier for a given `kzg10_vk`. The
+// circuit's constraint-system metadata (how many public inputs it takes)
+// and the KZG verifying key's two G2 elements are hardcoded into the
+// contract so no constructor arguments are needed; `verifyProof` performs
+// the pairing check via the `ecPairing` precompile (address `0x08`),
+// folding `publicInputs` into the G1 side via the `ecAdd`/`ecMul`
+// precompiles (`0x06`/`0x07`) first, and returns a `bool`.
+pub fn render_solidity_verifier(vk: &VerifierKey<Bn_256>, num_public_inputs: usize) -> String {
+    let mut vk_bytes = vec![];
+    vk.write(&mut vk_bytes).expect("vk serialization");
+    // The two G2 elements (`beta_h`, `h`) are serialized back-to-back at
+    // the start of `vk`'s `ToBytes` encoding -- reusing the same
+    // serialization convention `encode_calldata` already relies on, rather
+    // than assuming field names on the foreign `VerifierKey` type.
+    let [beta_h_x0, beta_h_x1, beta_h_y0, beta_h_y1] = g2_point_hex(&vk_bytes, 0);
+    let [h_x0, h_x1, h_y0, h_y1] = g2_point_hex(&vk_bytes, G2_POINT_BYTES);
+
+    // Per-index weights for `_decode`'s public-input accumulator, derived
+    // the same deterministic way as every other fixed constant table in
+    // this series (`mimc::round_constants`, `poseidon::round_constants`):
+    // folding a small counter into an otherwise-zero 32-byte buffer. Weights
+    // are fixed at codegen time (the rendered contract hardcodes them), and
+    // distinct per index -- a shared
+    // weight across indices degenerates back to an unweighted sum.
+    let public_input_weights: Vec<String> = (0..num_public_inputs)
+        .map(|i| {
+            let mut seed = [0u8; 32];
+            seed[0..8].copy_from_slice(b"sol-wt\0\0");
+            seed[8] = (i & 0xff) as u8;
+            seed[9] = ((i >> 8) & 0xff) as u8;
+            // Rendered as a 0x-prefixed hex uint256 literal, reduced mod the
+            // field/group order so it's always a legal Solidity constant.
+            let w = Fr::from_random_bytes(&seed).expect("weight seed must parse");
+            let mut w_bytes = vec![];
+            w.write(&mut w_bytes).expect("weight serialization");
+            let hex: String = w_bytes.iter().map(|b| format!("{:02x}", b)).collect();
+            format!("0x{}", hex)
+        })
+        .collect();
+    let weights_literal = public_input_weights.join(", ");
+
+    format!(
+        r#"// SPDX-License-Identifier: MIT
+// Generated by thumbnail-zkp; do not edit by hand.
+pragma solidity ^0.8.0;
+
+library Pairing {{
+    uint256 constant FIELD_MODULUS =
+        21888242871839275222246405745257275088696311157297823662689037894645226208583;
+
+    struct G1Point {{
+        uint256 x;
+        uint256 y;
+    }}
+
+    struct G2Point {{
+        uint256[2] x;
+        uint256[2] y;
+    }}
+
+    function negate(G1Point memory p) internal pure returns (G1Point memory) {{
+        if (p.x == 0 && p.y == 0) {{
+            return p;
+        }}
+        return G1Point(p.x, FIELD_MODULUS - (p.y % FIELD_MODULUS));
+    }}
+
+    function add(G1Point memory p1, G1Point memory p2) internal view returns (G1Point memory r) {{
+        uint256[4] memory input = [p1.x, p1.y, p2.x, p2.y];
+        bool success;
+        assembly {{
+            success := staticcall(gas(), 6, input, 0x80, r, 0x40)
+        }}
+        require(success, "Pairing.add: ecAdd call failed");
+    }}
+
+    function mul(G1Point memory p, uint256 s) internal view returns (G1Point memory r) {{
+        uint256[3] memory input = [p.x, p.y, s];
+        bool success;
+        assembly {{
+            success := staticcall(gas(), 7, input, 0x60, r, 0x40)
+        }}
+        require(success, "Pairing.mul: ecMul call failed");
+    }}
+
+    function pairing(
+        G1Point[] memory p1,
+        G2Point[] memory p2
+    ) internal view returns (bool) {{
+        require(p1.length == p2.length, "pairing: length mismatch");
+        uint256 elements = p1.length;
+        uint256 inputSize = elements * 6;
+        uint256[] memory input = new uint256[](inputSize);
+        for (uint256 i = 0; i < elements; i++) {{
+            input[i * 6 + 0] = p1[i].x;
+            input[i * 6 + 1] = p1[i].y;
+            input[i * 6 + 2] = p2[i].x[0];
+            input[i * 6 + 3] = p2[i].x[1];
+            input[i * 6 + 4] = p2[i].y[0];
+            input[i * 6 + 5] = p2[i].y[1];
+        }}
+        uint256[1] memory out;
+        bool success;
+        assembly {{
+            success := staticcall(
+                gas(),
+                8,
+                add(input, 0x20),
+                mul(inputSize, 0x20),
+                out,
+                0x20
+            )
+        }}
+        require(success, "pairing: ecPairing call failed");
+        return out[0] != 0;
+    }}
+}}
+
+contract ThumbnailVerifier {{
+    uint256 constant NUM_PUBLIC_INPUTS = {num_public_inputs};
+
+    // The KZG10 verifying key's two G2 elements, read out of `kzg10_vk` at
+    // codegen time.
+    function _betaH() private pure returns (Pairing.G2Point memory) {{
+        return Pairing.G2Point(
+            [{beta_h_x0}, {beta_h_x1}],
+            [{beta_h_y0}, {beta_h_y1}]
+        );
+    }}
+
+    function _h() private pure returns (Pairing.G2Point memory) {{
+        return Pairing.G2Point(
+            [{h_x0}, {h_x1}],
+            [{h_y0}, {h_y1}]
+        );
+    }}
+
+    // Fixed per-index weights for `_decode`'s public-input accumulator,
+    // derived from a per-index counter at codegen time and hardcoded like
+    // the G2 constants above.
+    function _weights() private pure returns (uint256[NUM_PUBLIC_INPUTS] memory) {{
+        return [{weights_literal}];
+    }}
+
+    // `proof` is the clinkv2/KZG10 proof produced by `create_random_proof`,
+    // serialized as in the proof bytes `encode_calldata` writes: the commitment
+    // `C` and the opening witness `W`, each a G1 point, back to back.
+    // `publicInputs` holds the `one` vector followed by the output/digest
+    // pixel commitments, matching `tile_ios`'s flattened layout.
+    function verifyProof(
+        bytes calldata proof,
+        uint256[] calldata publicInputs
+    ) external view returns (bool) {{
+        require(
+            publicInputs.length == NUM_PUBLIC_INPUTS,
+            "verifyProof: bad public input length"
+        );
+
+        (Pairing.G1Point[] memory p1, Pairing.G2Point[] memory p2) = _decode(
+            proof,
+            publicInputs
+        );
+        return Pairing.pairing(p1, p2);
+    }}
+
+    function _decode(
+        bytes calldata proof,
+        uint256[] calldata publicInputs
+    )
+        private
+        view
+        returns (Pairing.G1Point[] memory p1, Pairing.G2Point[] memory p2)
+    {{
+        Pairing.G1Point memory commitment = Pairing.G1Point(
+            _readUint(proof, 0),
+            _readUint(proof, 32)
+        );
+        Pairing.G1Point memory witness = Pairing.G1Point(
+            _readUint(proof, 64),
+            _readUint(proof, 96)
+        );
+
+        // Fold the public inputs into a single scalar, weighted per index
+        // by the fixed `_weights()` table, and subtract its multiple of
+        // the witness point from the commitment, so a proof verifies only
+        // against the specific `publicInputs` it was built for. Per-index
+        // weighting (instead of a flat sum) binds each entry to its
+        // position: two inputs that sum to the same total no longer fold
+        // to the same `combined` unless they also happen to agree under
+        // every distinct weight.
+        uint256[NUM_PUBLIC_INPUTS] memory weights = _weights();
+        uint256 combined = 0;
+        for (uint256 i = 0; i < publicInputs.length; i++) {{
+            combined = addmod(
+                combined,
+                mulmod(publicInputs[i], weights[i], Pairing.FIELD_MODULUS),
+                Pairing.FIELD_MODULUS
+            );
+        }}
+
+        p1 = new Pairing.G1Point[](2);
+        p2 = new Pairing.G2Point[](2);
+        p1[0] = Pairing.add(commitment, Pairing.negate(Pairing.mul(witness, combined)));
+        p1[1] = witness;
+        p2[0] = _betaH();
+        p2[1] = _h();
+    }}
+
+    function _readUint(
+        bytes calldata data,
+        uint256 offset
+    ) private pure returns (uint256 value) {{
+        value = uint256(bytes32(data[offset:offset + 32]));
+    }}
+}}
+"#,
+    )
+}
+
+// Serializes a `proof` and its public inputs (every row of `tile_io`, e.g.
+// the `one` vector, the output pixel commitments, and the digest, in that
+// order) into the byte layout `ThumbnailVerifier.verifyProof` expects: the
+// raw proof bytes followed by the public inputs as 32-byte big-endian
+// words. Takes every row so the encoded calldata always has as many words
+// as `NUM_PUBLIC_INPUTS` counts -- encoding a subset of the rows would
+// silently desync the two.
+pub fn encode_calldata(proof: &Proof<Bn_256>, tile_io: &[Vec<Fr>]) -> Vec<u8> {
+    let mut calldata = vec![];
+    proof.write(&mut calldata).expect("proof serialization");
+    for fr in tile_io.iter().flatten() {
+        fr.write(&mut calldata).expect("public input serialization");
+    }
+    calldata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+    use revm::{
+        db::InMemoryDB,
+        primitives::{Bytecode, ExecutionResult, Output, TransactTo, U256},
+        Evm,
+    };
+    use std::process::Command;
+    use zkp_toolkit::clinkv2::kzg10::{create_random_proof, ProveAssignment, KZG10};
+    use zkp_toolkit::clinkv2::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
+    use zkp_toolkit::math::{One, PrimeField};
+
+    // Single-copy circuit with two independent public inputs (`x`, `y`),
+    // just enough to exercise a genuine proof through the generated
+    // verifier -- and to let a test tamper with two entries at once --
+    // without pulling in the thumbnail circuit's pixel-sized witness.
+    struct Trivial<F: PrimeField> {
+        x: Option<F>,
+        y: Option<F>,
+    }
+
+    impl<F: PrimeField> ConstraintSynthesizer<F> for Trivial<F> {
+        fn generate_constraints<CS: ConstraintSystem<F>>(
+            self,
+            cs: &mut CS,
+            index: usize,
+        ) -> Result<(), SynthesisError> {
+            let one = cs.alloc_input(|| "one", || Ok(F::one()), index)?;
+            let x = cs.alloc_input(
+                || "x",
+                || self.x.ok_or(SynthesisError::AssignmentMissing),
+                index,
+            )?;
+            let y = cs.alloc_input(
+                || "y",
+                || self.y.ok_or(SynthesisError::AssignmentMissing),
+                index,
+            )?;
+            cs.enforce(|| "x = one", |lc| lc + x, |lc| lc + CS::one(), |lc| lc + one);
+            cs.enforce(|| "y = one", |lc| lc + y, |lc| lc + CS::one(), |lc| lc + one);
+            Ok(())
+        }
+    }
+
+    fn compile_verifier(sol: &str) -> Vec<u8> {
+        let dir = std::env::temp_dir().join("thumbnail_zkp_verifier_test");
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let sol_path = dir.join("ThumbnailVerifier.sol");
+        std::fs::write(&sol_path, sol).expect("write generated contract");
+
+        let status = Command::new("solc")
+            .args(["--bin", "--overwrite", "-o"])
+            .arg(&dir)
+            .arg(&sol_path)
+            .status()
+            .expect("solc must be installed to run this test");
+        assert!(status.success(), "ThumbnailVerifier.sol failed to compile");
+
+        let bin = std::fs::read_to_string(dir.join("ThumbnailVerifier.bin")).unwrap();
+        hex::decode(bin.trim()).expect("solc bytecode must be hex")
+    }
+
+    // Deploys `bytecode` on a throwaway in-memory EVM and calls
+    // `verifyProof(bytes,uint256[])` with `calldata`, returning the
+    // returned bool.
+    fn call_verify_proof(bytecode: &[u8], call_data: Vec<u8>) -> bool {
+        let mut db = InMemoryDB::default();
+        let mut evm = Evm::builder().with_db(&mut db).build();
+
+        evm.context.evm.env.tx.transact_to = TransactTo::Create;
+        evm.context.evm.env.tx.data = Bytecode::new_raw(bytecode.to_vec().into()).bytes();
+        let deployed = evm.transact_commit().expect("deploy transaction failed");
+        let contract = match deployed {
+            ExecutionResult::Success {
+                output: Output::Create(_, Some(address)),
+                ..
+            } => address,
+            other => panic!("deployment did not return a contract address: {:?}", other),
+        };
+
+        evm.context.evm.env.tx.transact_to = TransactTo::Call(contract);
+        evm.context.evm.env.tx.data = call_data.into();
+        let result = evm.transact_commit().expect("call transaction failed");
+        match result {
+            ExecutionResult::Success {
+                output: Output::Call(bytes),
+                ..
+            } => *bytes.last().expect("verifyProof must return a bool") != 0,
+            other => panic!("verifyProof call reverted: {:?}", other),
+        }
+    }
+
+    fn verify_proof_calldata(proof_and_inputs: &[u8], public_inputs: &[U256]) -> Vec<u8> {
+        // `verifyProof(bytes,uint256[])` selector, followed by the ABI
+        // encoding of its two arguments.
+        let selector = &keccak256(b"verifyProof(bytes,uint256[])")[0..4];
+        let mut data = selector.to_vec();
+        // Head: offset to `proof` (two words in), offset to `publicInputs`.
+        data.extend_from_slice(&U256::from(64).to_be_bytes::<32>());
+        let proof_tail_len = 32 + proof_and_inputs.len().div_ceil(32) * 32;
+        data.extend_from_slice(&U256::from(64 + proof_tail_len).to_be_bytes::<32>());
+        // `proof` tail: length then padded bytes.
+        data.extend_from_slice(&U256::from(proof_and_inputs.len()).to_be_bytes::<32>());
+        data.extend_from_slice(proof_and_inputs);
+        data.resize(data.len() + (32 - proof_and_inputs.len() % 32) % 32, 0);
+        // `publicInputs` tail: length then each word.
+        data.extend_from_slice(&U256::from(public_inputs.len()).to_be_bytes::<32>());
+        for word in public_inputs {
+            data.extend_from_slice(&word.to_be_bytes::<32>());
+        }
+        data
+    }
+
+    fn keccak256(data: &[u8]) -> [u8; 32] {
+        use sha3::{Digest, Keccak256};
+        Keccak256::digest(data).into()
+    }
+
+    #[test]
+    fn verifier_sol_accepts_genuine_proof_and_rejects_tampering() {
+        let mut rng = thread_rng();
+        let degree = 1usize.next_power_of_two();
+        let pp = KZG10::<Bn_256>::setup(degree, false, &mut rng).unwrap();
+        let (ck, vk) = KZG10::<Bn_256>::trim(&pp, degree).unwrap();
+
+        let mut pa = ProveAssignment::<Bn_256>::default();
+        let circuit = Trivial {
+            x: Some(Fr::one()),
+            y: Some(Fr::one()),
+        };
+        circuit.generate_constraints(&mut pa, 0).unwrap();
+        let proof = create_random_proof(&pa, &ck, &mut rng).unwrap();
+
+        let tile_io = vec![vec![Fr::one()], vec![Fr::one()], vec![Fr::one()]];
+        let num_public_inputs: usize = tile_io.iter().map(|row| row.len()).sum();
+        let calldata = encode_calldata(&proof, &tile_io);
+        let sol = render_solidity_verifier(&vk, num_public_inputs);
+        let bytecode = compile_verifier(&sol);
+
+        let mut proof_bytes = vec![];
+        proof.write(&mut proof_bytes).unwrap();
+        let public_inputs: Vec<U256> = tile_io
+            .iter()
+            .flatten()
+            .map(|fr| {
+                let mut buf = vec![];
+                fr.write(&mut buf).unwrap();
+                U256::from_be_slice(&buf)
+            })
+            .collect();
+
+        let genuine_call = verify_proof_calldata(&proof_bytes, &public_inputs);
+        assert!(
+            call_verify_proof(&bytecode, genuine_call),
+            "genuine proof must verify"
+        );
+
+        let mut tampered_inputs = public_inputs.clone();
+        tampered_inputs[1] += U256::from(1);
+        let tampered_call = verify_proof_calldata(&proof_bytes, &tampered_inputs);
+        assert!(
+            !call_verify_proof(&bytecode, tampered_call),
+            "proof must be rejected after tampering with a public input"
+        );
+
+        // Offsetting tamper: shift two entries in opposite directions so
+        // their flat sum is unchanged. A per-index-weighted accumulator
+        // must still reject this, where an unweighted sum would not.
+        let mut offsetting_inputs = public_inputs.clone();
+        offsetting_inputs[1] += U256::from(1);
+        offsetting_inputs[2] -= U256::from(1);
+        let offsetting_call = verify_proof_calldata(&proof_bytes, &offsetting_inputs);
+        assert!(
+            !call_verify_proof(&bytecode, offsetting_call),
+            "proof must be rejected after offsetting two public inputs in opposite directions"
+        );
+
+        // Not sent above: the calls are ABI-encoded from the same proof
+        // bytes and public-input words that `calldata` concatenates.
+        let _ = calldata;
+    }
+}